@@ -1,26 +1,65 @@
 // Work only with lcds that have hd44780 driver like lcd 1602
 
 #![no_std]
+use core::cell::RefCell;
 use defmt::*;
-use embassy_rp::{
-    bind_interrupts,
-    i2c::{ self, Async, Config, I2c, SclPin, SdaPin },
-    peripherals::I2C0,
-    Peri,
-};
-use embassy_time::Delay;
-use embedded_hal_1::delay::DelayNs;
+use embassy_rp::i2c::{ self, Async, I2c, Instance };
+use embassy_time::{ with_timeout, Delay, Duration, Instant, Timer };
+use embedded_hal::blocking::i2c::Write as BlockingI2cWrite;
+use embedded_hal_1::{ delay::DelayNs, i2c::{ I2c as _, Operation } };
 use hd44780_driver::{ bus::I2CBus, error::Error, Cursor, CursorBlink, Direction, Display, HD44780 };
 use itoa::Buffer; // For integers
 use ryu::Buffer as FloatBuffer; // For floats
 
+/// `hd44780-driver` 0.4 has no public way to issue a raw instruction-register
+/// write (`write_command` is private, and so is the bus it owns), so CGRAM
+/// addressing can't be reached through `HD44780`'s API at all. `Lcd` instead
+/// takes the I2C bus behind a `RefCell` it shares with the driver: the driver
+/// gets its own thin `SharedI2c` handle for everything it already supports,
+/// while `Lcd` keeps a second handle to hand-roll the PCF8574 nibble frames
+/// CGRAM setup needs, bypassing `HD44780` for just that one instruction.
+///
+/// `hd44780-driver` is built against embedded-hal **0.2**
+/// (`embedded_hal::blocking::i2c::Write`), not the 1.0 `i2c::I2c` this crate
+/// otherwise uses elsewhere, so `SharedI2c` implements both: the 0.2 trait
+/// for `HD44780::new_i2c` to build on, written in terms of the underlying
+/// `I2c`'s 1.0 `transaction`, and the 1.0 trait as a plain pass-through.
+/// `Lcd`'s own CGRAM path borrows the shared `I2c` directly, not through
+/// `SharedI2c`.
+struct SharedI2c<'a, I: Instance>(&'a RefCell<I2c<'a, I, Async>>);
+
+impl<'a, I: Instance> embedded_hal_1::i2c::ErrorType for SharedI2c<'a, I> {
+    type Error = i2c::Error;
+}
+
+impl<'a, I: Instance> embedded_hal_1::i2c::I2c for SharedI2c<'a, I> {
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [embedded_hal_1::i2c::Operation<'_>]
+    ) -> Result<(), Self::Error> {
+        self.0.borrow_mut().transaction(address, operations)
+    }
+}
+
+impl<'a, I: Instance> BlockingI2cWrite for SharedI2c<'a, I> {
+    type Error = i2c::Error;
+
+    fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.0.borrow_mut().transaction(address, &mut [Operation::Write(bytes)])
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum LcdError {
+    InitError,
     ClearError,
     ResetError,
     DisplayError,
     WriteError,
     CursorError,
+    TimeoutError,
+    OutOfBounds,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -29,56 +68,137 @@ pub enum CursorMoveDirection {
     Right,
 }
 
-bind_interrupts!(struct Irqs {
-    I2C0_IRQ => i2c::InterruptHandler<I2C0>;
-});
-
 macro_rules! lcd_try {
     ($expr:expr, $error:expr) => {
         $expr.map_err(|e| {
             // Optional: Log the error or perform custom handling
-            ($error, e)
+            ($error, Some(e))
         })?
     };
 }
-pub struct Lcd<'a> {
-    driver: HD44780<I2CBus<I2c<'a, I2C0, Async>>>,
+pub struct Lcd<'a, I: Instance> {
+    driver: HD44780<I2CBus<SharedI2c<'a, I>>>,
+    bus: &'a RefCell<I2c<'a, I, Async>>,
+    address: u8,
     delay: Delay,
     update_screen_time: u32,
+    cursor_pos: u8,
+    cols: u8,
+    rows: u8,
+    scroll_offset: usize,
+    command_timeout: Option<Duration>,
 }
-impl<'d> Lcd<'d> {
+impl<'d, I: Instance> Lcd<'d, I> {
+    /// `cols`/`rows` describe the panel geometry, e.g. `16, 2` for a
+    /// standard 1602 or `20, 4` for a 2004. `address` is the PCF8574
+    /// backpack's I2C address (commonly `0x27` or `0x3F`). `bus` is the I2C
+    /// peripheral the caller already constructed (via `I2c::new_async` with
+    /// whatever `bind_interrupts!` binding fits their instance) wrapped in a
+    /// `RefCell` so `Lcd` can share it between the `HD44780` driver and its
+    /// own raw CGRAM command path.
+    ///
+    /// This supersedes the earlier constructor that took `Peri`/`SclPin`/
+    /// `SdaPin`/an interrupt `Binding` directly and built the `I2c` itself:
+    /// CGRAM support needs a raw command path alongside `HD44780`, both
+    /// sharing the same bus, which isn't possible if `Lcd` owns the `I2c`
+    /// outright. Construction moved to the caller so both paths can borrow
+    /// it independently.
     pub async fn new(
-        i2c0: Peri<'d, I2C0>,
-        scl: Peri<'d, impl SclPin<I2C0>>,
-        sda: Peri<'d, impl SdaPin<I2C0>>,
-        update_screen_time: u32
-    ) -> Self {
-        let config = Config::default();
-
-        let i2c = I2c::new_async(i2c0, scl, sda, Irqs, config);
-
+        bus: &'d RefCell<I2c<'d, I, Async>>,
+        address: u8,
+        update_screen_time: u32,
+        cols: u8,
+        rows: u8
+    ) -> Result<Self, (LcdError, Option<Error>)> {
         let mut delay = Delay;
 
         delay.delay_ms(update_screen_time);
 
-        let mut lcd_driver = HD44780::new_i2c(i2c, 0x27, &mut delay).unwrap();
+        let mut lcd_driver = HD44780::new_i2c(SharedI2c(bus), address, &mut delay).map_err(
+            |e| (LcdError::InitError, Some(e))
+        )?;
 
         match Self::initialize_lcd(&mut lcd_driver, &mut delay) {
             Ok(_) => info!("LCD Initialized Successfully"),
             Err(_) => warn!("LCD Init Failed"),
         }
 
-        Self {
+        Ok(Self {
             driver: lcd_driver,
+            bus,
+            address,
             delay,
             update_screen_time: update_screen_time,
+            cursor_pos: 0,
+            cols,
+            rows,
+            scroll_offset: 0,
+            command_timeout: None,
+        })
+    }
+
+    /// Bounds how long operations may take before surfacing
+    /// `LcdError::TimeoutError` instead of quietly running long: the
+    /// cooperative inter-command wait in `throttle`, and cumulative time
+    /// spent in `HD44780` calls (checked via `bound` after each call
+    /// returns, and between iterations of multi-call loops like
+    /// `create_char`'s bitmap upload and `tick_scroll`'s per-column writes).
+    /// `None` (the default) disables all of the above.
+    ///
+    /// `hd44780-driver`'s calls are synchronous with no `.await` points, so
+    /// a single blocking I2C transaction that never completes (a bus wedged
+    /// mid-transfer, SDA/SCL stuck low) still can't be preempted by this —
+    /// there's nothing for the executor to interrupt until the call returns
+    /// control. What this *does* catch is the much more common failure mode:
+    /// a bus that's merely slow (retries, clock stretching) blowing well
+    /// past budget across a command or a sequence of them.
+    pub fn set_command_timeout(&mut self, timeout: Option<Duration>) {
+        self.command_timeout = timeout;
+    }
+
+    /// Cooperatively yields for `update_screen_time` between operations
+    /// instead of blocking the executor, so other tasks can run during the
+    /// inter-command spacing the HD44780 needs.
+    async fn throttle(&mut self) -> Result<(), (LcdError, Option<Error>)> {
+        let wait = Timer::after_millis(self.update_screen_time as u64);
+        match self.command_timeout {
+            Some(timeout) =>
+                with_timeout(timeout, wait).await.map_err(|_| (LcdError::TimeoutError, None)),
+            None => {
+                wait.await;
+                Ok(())
+            }
+        }
+    }
+
+    /// Wraps the result of a single blocking `HD44780` call: maps a
+    /// driver error through as before, but also turns a call that completed
+    /// late (slower than `command_timeout`, once one is set) into
+    /// `LcdError::TimeoutError` rather than silently returning `Ok`.
+    fn bound<T>(
+        &self,
+        start: Instant,
+        result: Result<T, Error>,
+        error: LcdError
+    ) -> Result<T, (LcdError, Option<Error>)> {
+        let value = result.map_err(|e| (error, Some(e)))?;
+        if self.command_timeout.is_some_and(|timeout| start.elapsed() > timeout) {
+            return Err((LcdError::TimeoutError, None));
         }
+        Ok(value)
+    }
+
+    /// Returns `true` once `command_timeout` (if set) has elapsed since
+    /// `start`, for bailing out of a multi-call loop before issuing the next
+    /// blocking write.
+    fn deadline_exceeded(&self, start: Instant) -> bool {
+        self.command_timeout.is_some_and(|timeout| start.elapsed() > timeout)
     }
 
     fn initialize_lcd(
-        lcd: &mut HD44780<I2CBus<I2c<'d, I2C0, Async>>>,
+        lcd: &mut HD44780<I2CBus<SharedI2c<'d, I>>>,
         delay: &mut Delay
-    ) -> Result<(), (LcdError, Error)> {
+    ) -> Result<(), (LcdError, Option<Error>)> {
         lcd_try!(lcd.clear(delay), LcdError::ClearError);
         lcd_try!(lcd.reset(delay), LcdError::ResetError);
         lcd_try!(lcd.set_display(Display::On, delay), LcdError::DisplayError);
@@ -92,149 +212,389 @@ impl<'d> Lcd<'d> {
         &mut self,
         text: &str,
         clear_display: bool
-    ) -> Result<(), (LcdError, Error)> {
-        self.delay.delay_ms(self.update_screen_time);
+    ) -> Result<(), (LcdError, Option<Error>)> {
+        self.throttle().await?;
         if clear_display {
             self.clear_display().await?;
         }
-        self.driver.write_str(text, &mut self.delay).map_err(|e| (LcdError::WriteError, e))
+        let start = Instant::now();
+        let result = self.driver.write_str(text, &mut self.delay);
+        self.bound(start, result, LcdError::WriteError)
     }
 
     pub async fn display_byte(
         &mut self,
         byte: u8,
         clear_display: bool
-    ) -> Result<(), (LcdError, Error)> {
-        self.delay.delay_ms(self.update_screen_time);
+    ) -> Result<(), (LcdError, Option<Error>)> {
+        self.throttle().await?;
         if clear_display {
             self.clear_display().await?;
         }
-        self.driver.write_byte(byte, &mut self.delay).map_err(|e| (LcdError::WriteError, e))
+        let start = Instant::now();
+        let result = self.driver.write_byte(byte, &mut self.delay);
+        self.bound(start, result, LcdError::WriteError)
     }
 
     pub async fn display_bytes(
         &mut self,
         bytes: &[u8],
         clear_display: bool
-    ) -> Result<(), (LcdError, Error)> {
-        self.delay.delay_ms(self.update_screen_time);
+    ) -> Result<(), (LcdError, Option<Error>)> {
+        self.throttle().await?;
         if clear_display {
             self.clear_display().await?;
         }
-        self.driver.write_bytes(bytes, &mut self.delay).map_err(|e| (LcdError::WriteError, e))
+        let start = Instant::now();
+        let result = self.driver.write_bytes(bytes, &mut self.delay);
+        self.bound(start, result, LcdError::WriteError)
     }
 
     pub async fn display_char(
         &mut self,
         char: char,
         clear_display: bool
-    ) -> Result<(), (LcdError, Error)> {
-        self.delay.delay_ms(self.update_screen_time);
+    ) -> Result<(), (LcdError, Option<Error>)> {
+        self.throttle().await?;
         if clear_display {
             self.clear_display().await?;
         }
-        self.driver.write_char(char, &mut self.delay).map_err(|e| (LcdError::WriteError, e))
+        let start = Instant::now();
+        let result = self.driver.write_char(char, &mut self.delay);
+        self.bound(start, result, LcdError::WriteError)
     }
 
     pub async fn display_int<T: itoa::Integer>(
         &mut self,
         num: T,
         clear_display: bool
-    ) -> Result<(), (LcdError, Error)> {
+    ) -> Result<(), (LcdError, Option<Error>)> {
         let mut buffer = Buffer::new();
         if clear_display {
             self.clear_display().await?;
         }
-        self.driver
-            .write_str(buffer.format(num), &mut self.delay)
-            .map_err(|e| (LcdError::WriteError, e))
+        let start = Instant::now();
+        let result = self.driver.write_str(buffer.format(num), &mut self.delay);
+        self.bound(start, result, LcdError::WriteError)
     }
 
     pub async fn display_float<T: ryu::Float>(
         &mut self,
         num: T,
         clear_display: bool
-    ) -> Result<(), (LcdError, Error)> {
+    ) -> Result<(), (LcdError, Option<Error>)> {
         let mut buffer = FloatBuffer::new();
         if clear_display {
             self.clear_display().await?;
         }
-        self.driver
-            .write_str(buffer.format(num), &mut self.delay)
-            .map_err(|e| (LcdError::WriteError, e))
+        let start = Instant::now();
+        let result = self.driver.write_str(buffer.format(num), &mut self.delay);
+        self.bound(start, result, LcdError::WriteError)
     }
 
-    pub async fn clear_display(&mut self) -> Result<(), (LcdError, Error)> {
-        self.driver.clear(&mut self.delay).map_err(|e| (LcdError::ClearError, e))
+    pub async fn clear_display(&mut self) -> Result<(), (LcdError, Option<Error>)> {
+        let start = Instant::now();
+        let result = self.driver.clear(&mut self.delay);
+        self.bound(start, result, LcdError::ClearError)
     }
 
-    pub async fn reset_display(&mut self) -> Result<(), (LcdError, Error)> {
-        self.delay.delay_ms(self.update_screen_time);
-        self.driver.reset(&mut self.delay).map_err(|e| (LcdError::ResetError, e))
+    pub async fn reset_display(&mut self) -> Result<(), (LcdError, Option<Error>)> {
+        self.throttle().await?;
+        let start = Instant::now();
+        let result = self.driver.reset(&mut self.delay);
+        self.bound(start, result, LcdError::ResetError)
     }
 
     pub async fn set_display_mode(
         &mut self,
         display_mode: Display
-    ) -> Result<(), (LcdError, Error)> {
-        self.delay.delay_ms(self.update_screen_time);
-        self.driver
-            .set_display(display_mode, &mut self.delay)
-            .map_err(|e| (LcdError::DisplayError, e))
+    ) -> Result<(), (LcdError, Option<Error>)> {
+        self.throttle().await?;
+        let start = Instant::now();
+        let result = self.driver.set_display(display_mode, &mut self.delay);
+        self.bound(start, result, LcdError::DisplayError)
     }
 
-    pub async fn set_cursor_visibility(&mut self, visible: bool) -> Result<(), (LcdError, Error)> {
-        self.delay.delay_ms(self.update_screen_time);
+    pub async fn set_cursor_visibility(&mut self, visible: bool) -> Result<(), (LcdError, Option<Error>)> {
+        self.throttle().await?;
         let visibillity: Cursor;
         if visible {
             visibillity = Cursor::Visible;
         } else {
             visibillity = Cursor::Invisible;
         }
-        self.driver
-            .set_cursor_visibility(visibillity, &mut self.delay)
-            .map_err(|e| (LcdError::CursorError, e))
+        let start = Instant::now();
+        let result = self.driver.set_cursor_visibility(visibillity, &mut self.delay);
+        self.bound(start, result, LcdError::CursorError)
     }
 
-    pub async fn set_cursor_blink(&mut self, blink: bool) -> Result<(), (LcdError, Error)> {
-        self.delay.delay_ms(self.update_screen_time);
+    pub async fn set_cursor_blink(&mut self, blink: bool) -> Result<(), (LcdError, Option<Error>)> {
+        self.throttle().await?;
         let cursor_blink: CursorBlink;
         if blink {
             cursor_blink = CursorBlink::On;
         } else {
             cursor_blink = CursorBlink::Off;
         }
-        self.driver
-            .set_cursor_blink(cursor_blink, &mut self.delay)
-            .map_err(|e| (LcdError::CursorError, e))
+        let start = Instant::now();
+        let result = self.driver.set_cursor_blink(cursor_blink, &mut self.delay);
+        self.bound(start, result, LcdError::CursorError)
     }
 
     pub async fn move_cursor_direction(
         &mut self,
         move_direction: CursorMoveDirection
-    ) -> Result<(), (LcdError, Error)> {
-        match move_direction {
-            CursorMoveDirection::Left => {
-                self.driver
-                    .shift_cursor(Direction::Left, &mut self.delay)
-                    .map_err(|e| (LcdError::CursorError, e))
+    ) -> Result<(), (LcdError, Option<Error>)> {
+        let start = Instant::now();
+        let result = match move_direction {
+            CursorMoveDirection::Left => self.driver.shift_cursor(Direction::Left, &mut self.delay),
+            CursorMoveDirection::Right => self.driver.shift_cursor(Direction::Right, &mut self.delay),
+        };
+        self.bound(start, result, LcdError::CursorError)
+    }
+
+    pub async fn set_autoscroll(&mut self, enable: bool) -> Result<(), (LcdError, Option<Error>)> {
+        let start = Instant::now();
+        let result = self.driver.set_autoscroll(enable, &mut self.delay);
+        self.bound(start, result, LcdError::CursorError)
+    }
+
+    pub async fn set_cursor_pos(&mut self, position: u8) -> Result<(), (LcdError, Option<Error>)> {
+        self.throttle().await?;
+        let start = Instant::now();
+        let result = self.driver.set_cursor_pos(position, &mut self.delay);
+        self.bound(start, result, LcdError::CursorError)?;
+        self.cursor_pos = position;
+        Ok(())
+    }
+
+    /// Positions the cursor at a given column/row instead of a raw linear
+    /// offset, accounting for the non-contiguous DDRAM addressing of
+    /// multi-row HD44780 panels. Errors with `LcdError::OutOfBounds` if
+    /// `col`/`row` falls outside the panel geometry given to `Lcd::new`.
+    pub async fn set_cursor(&mut self, col: u8, row: u8) -> Result<(), (LcdError, Option<Error>)> {
+        if !cursor_in_bounds(self.cols, self.rows, col, row) {
+            return Err((LcdError::OutOfBounds, None));
+        }
+        let address = 0x80 | (row_base_address(self.cols, row) + col);
+        self.set_cursor_pos(address).await
+    }
+
+    /// Positions the cursor at `(col, row)` and writes `text` there, e.g. to
+    /// update a single field without clearing the whole screen.
+    pub async fn display_text_at(
+        &mut self,
+        col: u8,
+        row: u8,
+        text: &str
+    ) -> Result<(), (LcdError, Option<Error>)> {
+        self.set_cursor(col, row).await?;
+        let start = Instant::now();
+        let result = self.driver.write_str(text, &mut self.delay);
+        self.bound(start, result, LcdError::WriteError)
+    }
+
+    /// Starts a horizontally scrolling marquee of `text` on `row`, resetting
+    /// the scroll position to the beginning. `text` is usually longer than
+    /// the configured column count. Call `tick_scroll` afterward, e.g. from
+    /// a timer loop, to keep advancing it.
+    pub async fn scroll_text(&mut self, text: &str, row: u8) -> Result<(), (LcdError, Option<Error>)> {
+        self.scroll_offset = 0;
+        self.tick_scroll(text, row).await
+    }
+
+    /// Redraws the current scroll window for `text` on `row` and advances
+    /// the offset by one column, wrapping around with a few blank padding
+    /// columns so the marquee loops cleanly.
+    pub async fn tick_scroll(&mut self, text: &str, row: u8) -> Result<(), (LcdError, Option<Error>)> {
+        let cols = self.cols as usize;
+        let len = text.chars().count();
+        let period = scroll_period(cols, len);
+
+        self.set_cursor(0, row).await?;
+        let start = Instant::now();
+        for i in 0..cols {
+            if self.deadline_exceeded(start) {
+                return Err((LcdError::TimeoutError, None));
             }
-            CursorMoveDirection::Right => {
-                self.driver
-                    .shift_cursor(Direction::Right, &mut self.delay)
-                    .map_err(|e| (LcdError::CursorError, e))
+            let idx = (self.scroll_offset + i) % period;
+            let ch = if idx < len { text.chars().nth(idx).unwrap_or(' ') } else { ' ' };
+            lcd_try!(self.driver.write_char(ch, &mut self.delay), LcdError::WriteError);
+        }
+
+        self.scroll_offset = (self.scroll_offset + 1) % period;
+        Ok(())
+    }
+
+    /// Sends a single 4-bit nibble over the PCF8574 I2C backpack with an
+    /// enable-line pulse, the wire-level primitive `HD44780` never exposes.
+    /// Matches `hd44780-driver`'s own `I2CBus::write_nibble` timing: a 2ms
+    /// delay between raising and dropping `ENABLE`, rather than relying on
+    /// I2C transaction latency alone to clear the pulse width.
+    fn write_nibble(&mut self, nibble: u8, register_select: bool) -> Result<(), (LcdError, Option<Error>)> {
+        const BACKLIGHT: u8 = 0x08;
+        const ENABLE: u8 = 0x04;
+        const RS: u8 = 0x01;
+
+        let rs_bit = if register_select { RS } else { 0 };
+        let base = ((nibble & 0x0f) << 4) | rs_bit | BACKLIGHT;
+        {
+            let mut bus = self.bus.borrow_mut();
+            bus.write(self.address, &[base | ENABLE]).map_err(|_| (LcdError::WriteError, None))?;
+        }
+        self.delay.delay_ms(2);
+        let mut bus = self.bus.borrow_mut();
+        bus.write(self.address, &[base]).map_err(|_| (LcdError::WriteError, None))
+    }
+
+    /// Issues a raw instruction-register write by hand-rolling the PCF8574
+    /// nibble frames directly, bypassing `HD44780` entirely. Needed because
+    /// `hd44780-driver` keeps its own equivalent (`write_command`) and its
+    /// bus private, so there is no public way to ask it for an arbitrary
+    /// instruction such as "Set CGRAM Address".
+    fn write_raw_command(&mut self, command: u8) -> Result<(), (LcdError, Option<Error>)> {
+        let start = Instant::now();
+        self.write_nibble(command >> 4, false)?;
+        if self.deadline_exceeded(start) {
+            return Err((LcdError::TimeoutError, None));
+        }
+        self.write_nibble(command & 0x0f, false)
+    }
+
+    /// Uploads an 8-byte 5x8 glyph into one of the eight CGRAM slots (0-7).
+    ///
+    /// Only the low 5 bits of each row byte are used, bit4 being the
+    /// leftmost pixel. This leaves the address counter pointed at CGRAM, so
+    /// any subsequent text write must go through `display_custom_char` or
+    /// `set_cursor_pos` first.
+    pub async fn create_char(&mut self, slot: u8, bitmap: [u8; 8]) -> Result<(), (LcdError, Option<Error>)> {
+        self.throttle().await?;
+        let cgram_address = 0x40 | ((slot & 0x07) << 3);
+        self.write_raw_command(cgram_address)?;
+        let start = Instant::now();
+        for row in bitmap {
+            if self.deadline_exceeded(start) {
+                return Err((LcdError::TimeoutError, None));
             }
+            lcd_try!(self.driver.write_byte(row, &mut self.delay), LcdError::WriteError);
         }
+        Ok(())
     }
 
-    pub async fn set_autoscroll(&mut self, enable: bool) -> Result<(), (LcdError, Error)> {
-        self.driver.set_autoscroll(enable, &mut self.delay).map_err(|e| (LcdError::CursorError, e))
+    /// Restores DDRAM addressing to the position last set with
+    /// `set_cursor_pos` (or `set_cursor`), then renders a glyph previously
+    /// uploaded with `create_char`. Text written since then, and the glyph
+    /// written here, do not move that stored position. The order
+    /// matters: `create_char` leaves the address counter pointed at CGRAM,
+    /// so writing the render byte before restoring DDRAM addressing would
+    /// land back in CGRAM and corrupt the glyph just uploaded.
+    pub async fn display_custom_char(&mut self, slot: u8) -> Result<(), (LcdError, Option<Error>)> {
+        self.throttle().await?;
+        let start = Instant::now();
+        let result = self.driver.set_cursor_pos(self.cursor_pos, &mut self.delay);
+        self.bound(start, result, LcdError::CursorError)?;
+        if self.deadline_exceeded(start) {
+            return Err((LcdError::TimeoutError, None));
+        }
+        lcd_try!(self.driver.write_byte(slot & 0x07, &mut self.delay), LcdError::WriteError);
+        Ok(())
     }
 
-    pub async fn set_cursor_pos(&mut self, position: u8) -> Result<(), (LcdError, Error)> {
+    /// Blocking `write!`/`writeln!` entry point, e.g. `write!(lcd, "T:{:.1}C", temp)`.
+    ///
+    /// Formats straight into the display in one pass instead of going
+    /// through `display_int`/`display_float`/etc. for each piece, so mixed
+    /// text+numeric lines only pay `update_screen_time` once.
+    pub fn write_fmt(&mut self, args: core::fmt::Arguments) -> Result<(), LcdError> {
+        // Writes each formatted piece straight to the driver, so the
+        // `update_screen_time` delay below is paid once per call rather than
+        // once per piece as the `core::fmt::Write::write_str` path does.
+        struct Unthrottled<'a, 'd, I: Instance>(&'a mut Lcd<'d, I>);
+        impl<'a, 'd, I: Instance> core::fmt::Write for Unthrottled<'a, 'd, I> {
+            fn write_str(&mut self, s: &str) -> core::fmt::Result {
+                let lcd = &mut *self.0;
+                lcd.driver.write_str(s, &mut lcd.delay).map_err(|_| core::fmt::Error)
+            }
+        }
+
         self.delay.delay_ms(self.update_screen_time);
-        self.driver
-            .set_cursor_pos(position, &mut self.delay)
-            .map_err(|e| (LcdError::CursorError, e))
+        core::fmt::Write::write_fmt(&mut Unthrottled(self), args).map_err(|_| LcdError::WriteError)
+    }
+}
+
+impl<'d, I: Instance> core::fmt::Write for Lcd<'d, I> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        // `core::fmt::Write` is inherently synchronous, so this path still
+        // blocks for `update_screen_time`; use `display_text` when
+        // cooperative scheduling matters.
+        self.delay.delay_ms(self.update_screen_time);
+        self.driver.write_str(s, &mut self.delay).map_err(|_| core::fmt::Error)
+    }
+}
+
+/// Row base address for the standard HD44780 DDRAM layout, given the
+/// panel's configured column count. Pulled out of `Lcd` as a free function
+/// so it's testable without a live `HD44780`/I2C bus.
+fn row_base_address(cols: u8, row: u8) -> u8 {
+    match row % 4 {
+        0 => 0x00,
+        1 => 0x40,
+        2 => cols,
+        _ => 0x40 + cols,
+    }
+}
+
+/// Whether `(col, row)` falls within a panel of `cols` columns and `rows`
+/// rows.
+fn cursor_in_bounds(cols: u8, rows: u8, col: u8, row: u8) -> bool {
+    col < cols && row < rows
+}
+
+/// The scroll period `tick_scroll` advances `scroll_offset` through:
+/// always at least `cols` wide, so a single draw never revisits the same
+/// offset twice (which would render short text repeated across the row
+/// instead of padded out with blanks), and otherwise `len` plus a few
+/// blank padding columns so a marquee longer than the panel loops cleanly.
+fn scroll_period(cols: usize, len: usize) -> usize {
+    let padding = 3usize.min(cols);
+    (len + padding).max(cols).max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ cursor_in_bounds, row_base_address, scroll_period };
+
+    #[test]
+    fn row_base_address_cycles_through_the_four_ddram_banks() {
+        assert_eq!(row_base_address(16, 0), 0x00);
+        assert_eq!(row_base_address(16, 1), 0x40);
+        assert_eq!(row_base_address(16, 2), 16);
+        assert_eq!(row_base_address(16, 3), 0x40 + 16);
+        // Row indices past 3 wrap back around to row 0's base.
+        assert_eq!(row_base_address(20, 4), row_base_address(20, 0));
+    }
+
+    #[test]
+    fn cursor_in_bounds_rejects_columns_and_rows_past_panel_geometry() {
+        assert!(cursor_in_bounds(16, 2, 0, 0));
+        assert!(cursor_in_bounds(16, 2, 15, 1));
+        assert!(!cursor_in_bounds(16, 2, 16, 0));
+        assert!(!cursor_in_bounds(16, 2, 0, 2));
+    }
+
+    #[test]
+    fn scroll_period_is_never_shorter_than_the_panel_width() {
+        // Regression test for 74f4272: short text used to produce a period
+        // shorter than `cols`, which made `tick_scroll` repeat the message
+        // across the row instead of padding it with blanks.
+        assert_eq!(scroll_period(16, 2), 16);
+        assert_eq!(scroll_period(16, 0), 16);
+    }
+
+    #[test]
+    fn scroll_period_grows_with_long_text_past_the_panel_width() {
+        // text + padding, once that exceeds cols, drives the period so the
+        // marquee keeps scrolling instead of getting clamped to the panel.
+        assert_eq!(scroll_period(16, 30), 33);
     }
 }